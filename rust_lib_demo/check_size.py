@@ -0,0 +1,125 @@
+#!/usr/bin/python3
+
+# Check the linked image against per board limits, using the GNU ld map file.
+#
+# Everything placed below _stack_top (see powerpc.lds) is counted as code,
+# .bss* as zeroed data and everything else above it as initialised data.
+# Prints how many bytes of each kind every linked input file contributes,
+# then fails if a budget is exceeded, if anything below _stack_top ends in
+# the stack reserve, or if anything ends beyond the end of RAM.
+
+import sys
+import re
+import argparse
+from collections import defaultdict
+
+parser = argparse.ArgumentParser()
+parser.add_argument('map')
+parser.add_argument('--text-budget', type=lambda x: int(x, 0), required=True)
+parser.add_argument('--data-budget', type=lambda x: int(x, 0), required=True)
+parser.add_argument('--bss-budget', type=lambda x: int(x, 0), required=True)
+parser.add_argument('--stack-reserve', type=lambda x: int(x, 0), required=True)
+parser.add_argument('--ram-size', type=lambda x: int(x, 0), required=True)
+args = parser.parse_args()
+
+# Output sections which are not loaded
+not_alloc = ('.debug', '.comment', '.note', '.gnu.attributes', '.stab',
+             '.symtab', '.strtab', '.shstrtab')
+
+out_section = re.compile(r'^(\.\S+)(?:\s+0x([0-9a-f]+)\s+0x([0-9a-f]+))?')
+in_section = re.compile(r'^ (\.\S+|COMMON)(?:\s+0x([0-9a-f]+)\s+0x([0-9a-f]+)\s+(.*))?$')
+addr_size = re.compile(r'^\s+0x([0-9a-f]+)\s+0x([0-9a-f]+)(?:\s+(.*))?$')
+stack_top_sym = re.compile(r'^\s+0x([0-9a-f]+)\s+_stack_top = ')
+
+sections = []		# (name, addr, size)
+contrib = []		# (output section index, file, size)
+stack_top = None
+
+with open(args.map) as f:
+    lines = iter(f.read().splitlines())
+
+for line in lines:
+    if line.startswith('Linker script and memory map'):
+        break
+
+pending_out = None
+pending_in = False
+for line in lines:
+    m = stack_top_sym.match(line)
+    if m:
+        stack_top = int(m.group(1), 16)
+        continue
+    if pending_out is not None or pending_in:
+        # Long section names put the address and size on the next line
+        m = addr_size.match(line)
+        if m:
+            if pending_out is not None:
+                sections.append((pending_out, int(m.group(1), 16), int(m.group(2), 16)))
+            elif m.group(3):
+                contrib.append((len(sections) - 1, m.group(3).strip(), int(m.group(2), 16)))
+        pending_out = None
+        pending_in = False
+        if m:
+            continue
+    m = out_section.match(line)
+    if m:
+        if m.group(2) is None:
+            pending_out = m.group(1)
+        else:
+            sections.append((m.group(1), int(m.group(2), 16), int(m.group(3), 16)))
+        continue
+    m = in_section.match(line)
+    if m and sections:
+        if m.group(2) is None:
+            pending_in = True
+        else:
+            contrib.append((len(sections) - 1, m.group(4).strip(), int(m.group(3), 16)))
+
+if stack_top is None:
+    print("%s: no _stack_top symbol" % args.map)
+    sys.exit(1)
+
+def kind(name, addr):
+    if name.startswith('.bss') or name.startswith('.sbss'):
+        return 'bss'
+    if addr < stack_top:
+        return 'text'
+    return 'data'
+
+alloc = [s for s in sections if s[2] and not s[0].startswith(not_alloc)]
+
+per_file = defaultdict(lambda: defaultdict(int))
+for (i, file, size) in contrib:
+    (name, addr, _) = sections[i]
+    if size and not name.startswith(not_alloc):
+        per_file[file][kind(name, addr)] += size
+
+print("%8s %8s %8s  %s" % ("text", "data", "bss", "file"))
+for (file, sizes) in sorted(per_file.items(), key=lambda x: -sum(x[1].values())):
+    print("%8d %8d %8d  %s" % (sizes['text'], sizes['data'], sizes['bss'], file))
+
+totals = defaultdict(int)
+text_end = 0
+end = 0
+for (name, addr, size) in alloc:
+    totals[kind(name, addr)] += size
+    if addr < stack_top:
+        text_end = max(text_end, addr + size)
+    end = max(end, addr + size)
+
+fail = False
+def check(what, used, limit):
+    global fail
+    print("%-10s %#8x / %#8x" % (what, used, limit))
+    if used > limit:
+        fail = True
+
+check("text", totals['text'], args.text_budget)
+check("data", totals['data'], args.data_budget)
+check("bss", totals['bss'], args.bss_budget)
+check("text end", text_end, stack_top - args.stack_reserve)
+check("image end", end, args.ram_size)
+
+if fail:
+    print("size or placement budget exceeded")
+    sys.exit(1)
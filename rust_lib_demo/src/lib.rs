@@ -17,6 +17,8 @@ use alloc::vec::Vec;
 
 extern crate cty;
 
+mod trap;
+
 extern "C" {
     fn putchar(c: cty::c_char) -> ();
     fn crash() -> ();
@@ -0,0 +1,100 @@
+use core::fmt::Write;
+
+use heapless::consts::*;
+use heapless::String;
+
+use crate::print;
+
+/// Register state saved by `__trap` in head.S. The layout must match the
+/// SAVE_* offsets there.
+#[repr(C)]
+pub struct TrapFrame {
+    pub gpr: [u64; 32],
+    pub srr0: u64,
+    pub srr1: u64,
+    pub hsrr0: u64,
+    pub hsrr1: u64,
+    pub dar: u64,
+    pub dsisr: u64,
+    pub lr: u64,
+    pub ctr: u64,
+    pub cr: u64,
+    pub xer: u64,
+    pub vector: u64,
+}
+
+fn vector_name(vector: u64) -> &'static str {
+    match vector {
+        0x100 => "system reset",
+        0x200 => "machine check",
+        0x300 => "data storage",
+        0x380 => "data segment",
+        0x400 => "instruction storage",
+        0x480 => "instruction segment",
+        0x500 => "external",
+        0x600 => "alignment",
+        0x700 => "program check",
+        0x800 => "FP unavailable",
+        0x900 => "decrementer",
+        0x980 => "hypervisor decrementer",
+        0xa00 => "doorbell",
+        0xb00 => "reserved",
+        0xc00 => "system call",
+        0xd00 => "trace",
+        0xe00 => "hypervisor data storage",
+        0xe20 => "hypervisor instruction storage",
+        0xe40 => "hypervisor emulation assistance",
+        0xe60 => "hypervisor maintenance",
+        0xe80 => "directed hypervisor doorbell",
+        0xf00 => "performance monitor",
+        0xf20 => "vector unavailable",
+        0xf40 => "VSX unavailable",
+        0xf60 => "facility unavailable",
+        0xf80 => "hypervisor facility unavailable",
+        _ => "unknown",
+    }
+}
+
+/// Called from head.S for any exception. Nothing in this demo expects one,
+/// so report the saved state on the console and halt.
+#[no_mangle]
+pub extern "C" fn rust_trap(frame: &TrapFrame) -> ! {
+    let mut s: String<U128> = String::new();
+    writeln!(
+        s,
+        "\r\n!exception {:#x} ({})\r",
+        frame.vector,
+        vector_name(frame.vector)
+    )
+    .ok();
+    print(&s);
+
+    // Microwatt saves interrupt state in SRR0/1 even for the hypervisor
+    // vectors, so show both pairs rather than guessing
+    s.clear();
+    writeln!(s, "SRR0  {:016x} SRR1  {:016x}\r", frame.srr0, frame.srr1).ok();
+    print(&s);
+    s.clear();
+    writeln!(s, "HSRR0 {:016x} HSRR1 {:016x}\r", frame.hsrr0, frame.hsrr1).ok();
+    print(&s);
+    s.clear();
+    writeln!(s, "DAR   {:016x} DSISR {:08x}\r", frame.dar, frame.dsisr).ok();
+    print(&s);
+    s.clear();
+    writeln!(s, "LR    {:016x} CTR   {:016x}\r", frame.lr, frame.ctr).ok();
+    print(&s);
+    s.clear();
+    writeln!(s, "CR    {:08x}         XER   {:016x}\r", frame.cr, frame.xer).ok();
+    print(&s);
+
+    for (i, regs) in frame.gpr.chunks(4).enumerate() {
+        s.clear();
+        for (j, r) in regs.iter().enumerate() {
+            write!(s, "r{:<2} {:016x} ", i * 4 + j, r).ok();
+        }
+        writeln!(s, "\r").ok();
+        print(&s);
+    }
+
+    loop {}
+}